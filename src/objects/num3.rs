@@ -15,6 +15,25 @@ use objects::{exc, PyObjectRef};
 use instance::PyObjectWithToken;
 use conversion::{ToPyObject, IntoPyObject, FromPyObject};
 
+/// Like [`FromPyObject`](../conversion/trait.FromPyObject.html), but never
+/// coerces: the source object must already be a Python `int`
+/// (`PyLong_Check`), or a `TypeError` is raised.
+///
+/// Some of the `FromPyObject` impls in this module accept a wider set of
+/// inputs for convenience: those built on the `int_convert_u64_or_i64!`
+/// macro (`u64`, and `i64` where `c_long` is 32 bits), and those that
+/// delegate to them (such as `usize`), run anything that isn't already an
+/// `int` through `PyNumber_Long`, so e.g. `extract::<u64>()` on a Python `float`
+/// truncates instead of erroring. Those built on `int_fits_c_long!` hand the
+/// object straight to `PyLong_AsLong`, which does its own conversion of
+/// objects that aren't `int`s. For every integer type, `extract_exact` checks
+/// `PyLong_Check` before converting.
+pub trait FromPyObjectExact<'source>: Sized {
+    /// Extracts `Self` from `ob`, requiring `ob` to already be a Python
+    /// `int` rather than coercing it via `__int__`/`PyNumber_Long`.
+    fn extract_exact(ob: &'source PyObjectRef) -> PyResult<Self>;
+}
+
 /// Represents a Python `int` object.
 ///
 /// You can usually avoid directly working with this type
@@ -26,6 +45,31 @@ pub struct PyLong(PyObject);
 pyobject_convert!(PyLong);
 pyobject_nativetype!(PyLong, PyLong_Type, PyLong_Check);
 
+/// Represents a Python `float` object.
+///
+/// You can usually avoid directly working with this type
+/// by using [`ToPyObject`](trait.ToPyObject.html)
+/// and [extract](struct.PyObject.html#method.extract)
+/// with `f32`/`f64`.
+pub struct PyFloat(PyObject);
+
+pyobject_convert!(PyFloat);
+pyobject_nativetype!(PyFloat, PyFloat_Type, PyFloat_Check);
+
+impl PyFloat {
+    /// Creates a new Python `float` object.
+    pub fn new(py: Python, value: f64) -> PyFloat {
+        unsafe {
+            PyFloat(PyObject::from_owned_ptr_or_panic(py, ffi::PyFloat_FromDouble(value)))
+        }
+    }
+
+    /// Gets the value of this float.
+    pub fn value(&self) -> f64 {
+        unsafe { ffi::PyFloat_AsDouble(self.as_ptr()) }
+    }
+}
+
 
 macro_rules! int_fits_c_long(
     ($rust_type:ty) => (
@@ -53,6 +97,16 @@ macro_rules! int_fits_c_long(
                 None => Err(exc::OverflowError.into())
             }
         });
+        // `PyLong_AsLong` converts non-`int` objects itself, so the strict
+        // path checks the type before delegating to it.
+        impl<'source> FromPyObjectExact<'source> for $rust_type {
+            fn extract_exact(ob: &'source PyObjectRef) -> PyResult<$rust_type> {
+                if unsafe { ffi::PyLong_Check(ob.as_ptr()) } == 0 {
+                    return Err(exc::TypeError.into());
+                }
+                FromPyObject::extract(ob)
+            }
+        }
     )
 );
 
@@ -77,6 +131,15 @@ macro_rules! int_fits_larger_int(
                 None => Err(exc::OverflowError.into())
             }
         });
+        impl<'source> FromPyObjectExact<'source> for $rust_type {
+            fn extract_exact(ob: &'source PyObjectRef) -> PyResult<$rust_type> {
+                let val = try!(<$larger_type as FromPyObjectExact>::extract_exact(ob));
+                match cast::<$larger_type, $rust_type>(val) {
+                    Some(v) => Ok(v),
+                    None => Err(exc::OverflowError.into())
+                }
+            }
+        }
     )
 );
 
@@ -109,6 +172,8 @@ macro_rules! int_convert_u64_or_i64 (
                 }
             }
         }
+        // Coercing: non-`int` input (e.g. a `float`, or an object with
+        // `__int__`) is run through `PyNumber_Long` first.
         impl<'source> FromPyObject<'source> for $rust_type {
             fn extract(ob: &'source PyObjectRef) -> PyResult<$rust_type>
             {
@@ -127,6 +192,18 @@ macro_rules! int_convert_u64_or_i64 (
                 }
             }
         }
+        // Strict: rejects anything that isn't already a Python `int`.
+        impl<'source> FromPyObjectExact<'source> for $rust_type {
+            fn extract_exact(ob: &'source PyObjectRef) -> PyResult<$rust_type> {
+                let ptr = ob.as_ptr();
+                unsafe {
+                    if ffi::PyLong_Check(ptr) == 0 {
+                        return Err(exc::TypeError.into());
+                    }
+                    err_if_invalid_value(ob.py(), !0, $pylong_as_ull_or_ull(ptr))
+                }
+            }
+        }
     )
 );
 
@@ -161,11 +238,229 @@ int_fits_larger_int!(usize, u64);
 int_convert_u64_or_i64!(u64, ffi::PyLong_FromUnsignedLongLong, ffi::PyLong_AsUnsignedLongLong);
 
 
+// i128/u128 don't have a CPython `PyLong_As/FromXxx` pair, so we round-trip
+// through the arbitrary-width byte-array API instead.
+macro_rules! int_convert_128 (
+    ($rust_type:ty, $is_signed:expr) => (
+        impl ToPyObject for $rust_type {
+            #[inline]
+            fn to_object(&self, py: Python) -> PyObject {
+                (*self).into_object(py)
+            }
+        }
+        impl IntoPyObject for $rust_type {
+            fn into_object(self, py: Python) -> PyObject {
+                let buffer = self.to_le_bytes();
+                unsafe {
+                    PyObject::from_owned_ptr_or_panic(
+                        py,
+                        ffi::_PyLong_FromByteArray(buffer.as_ptr(), buffer.len(), 1, $is_signed))
+                }
+            }
+        }
+        impl<'source> FromPyObject<'source> for $rust_type {
+            fn extract(ob: &'source PyObjectRef) -> PyResult<$rust_type>
+            {
+                let ptr = ob.as_ptr();
+                unsafe {
+                    if ffi::PyLong_Check(ptr) == 0 {
+                        return Err(exc::TypeError.into());
+                    }
+                    let mut buffer = [0u8; 16];
+                    let result = ffi::_PyLong_AsByteArray(
+                        ptr as *mut ffi::PyLongObject, buffer.as_mut_ptr(), buffer.len(), 1, $is_signed);
+                    if result == -1 && PyErr::occurred(ob.py()) {
+                        return Err(PyErr::fetch(ob.py()));
+                    }
+                    Ok(<$rust_type>::from_le_bytes(buffer))
+                }
+            }
+        }
+        // `FromPyObject` above is already strict (no `PyNumber_Long`
+        // coercion), so the exact path is the same.
+        impl<'source> FromPyObjectExact<'source> for $rust_type {
+            fn extract_exact(ob: &'source PyObjectRef) -> PyResult<$rust_type> {
+                FromPyObject::extract(ob)
+            }
+        }
+    )
+);
+
+int_convert_128!(i128, 1);
+int_convert_128!(u128, 0);
+
+
+// Python's `int` is unbounded; these impls let arbitrary-precision values
+// round-trip without being truncated to a fixed Rust width.
+#[cfg(feature = "num-bigint")]
+extern crate num_bigint;
+
+#[cfg(feature = "num-bigint")]
+use self::num_bigint::{BigInt, BigUint, Sign};
+
+#[cfg(feature = "num-bigint")]
+impl ToPyObject for BigUint {
+    fn to_object(&self, py: Python) -> PyObject {
+        let bytes = self.to_bytes_le();
+        unsafe {
+            PyObject::from_owned_ptr_or_panic(
+                py,
+                ffi::_PyLong_FromByteArray(bytes.as_ptr(), bytes.len(), 1, 0))
+        }
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl IntoPyObject for BigUint {
+    fn into_object(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl<'source> FromPyObject<'source> for BigUint {
+    fn extract(ob: &'source PyObjectRef) -> PyResult<BigUint> {
+        let ptr = ob.as_ptr();
+        unsafe {
+            if ffi::PyLong_Check(ptr) == 0 {
+                return Err(exc::TypeError.into());
+            }
+            let mut buffer = try!(bignum_buffer(ob.py(), ptr));
+            let result = ffi::_PyLong_AsByteArray(
+                ptr as *mut ffi::PyLongObject, buffer.as_mut_ptr(), buffer.len(), 1, 0);
+            if result == -1 && PyErr::occurred(ob.py()) {
+                return Err(PyErr::fetch(ob.py()));
+            }
+            Ok(BigUint::from_bytes_le(&buffer))
+        }
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl ToPyObject for BigInt {
+    fn to_object(&self, py: Python) -> PyObject {
+        let (sign, bytes) = self.to_bytes_le();
+        unsafe {
+            let magnitude = PyObject::from_owned_ptr_or_panic(
+                py,
+                ffi::_PyLong_FromByteArray(bytes.as_ptr(), bytes.len(), 1, 0));
+            if sign == Sign::Minus {
+                PyObject::from_owned_ptr_or_panic(py, ffi::PyNumber_Negative(magnitude.as_ptr()))
+            } else {
+                magnitude
+            }
+        }
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl IntoPyObject for BigInt {
+    fn into_object(self, py: Python) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl<'source> FromPyObject<'source> for BigInt {
+    fn extract(ob: &'source PyObjectRef) -> PyResult<BigInt> {
+        let ptr = ob.as_ptr();
+        unsafe {
+            if ffi::PyLong_Check(ptr) == 0 {
+                return Err(exc::TypeError.into());
+            }
+            let mut buffer = try!(bignum_buffer(ob.py(), ptr));
+            let result = ffi::_PyLong_AsByteArray(
+                ptr as *mut ffi::PyLongObject, buffer.as_mut_ptr(), buffer.len(), 1, 1);
+            if result == -1 && PyErr::occurred(ob.py()) {
+                return Err(PyErr::fetch(ob.py()));
+            }
+            Ok(BigInt::from_signed_bytes_le(&buffer))
+        }
+    }
+}
+
+/// Allocate a buffer large enough to hold `_PyLong_AsByteArray`'s output for
+/// the Python int at `ptr`: `ceil(bits/8)` for the magnitude, plus one extra
+/// byte so a positive value with its top bit set still has room for the
+/// leading zero that keeps it from reading as negative.
+#[cfg(feature = "num-bigint")]
+unsafe fn bignum_buffer(py: Python, ptr: *mut ffi::PyObject) -> PyResult<Vec<u8>> {
+    let bits = ffi::_PyLong_NumBits(ptr);
+    if bits == (-1isize as usize) && PyErr::occurred(py) {
+        return Err(PyErr::fetch(py));
+    }
+    Ok(vec![0u8; (bits + 7) / 8 + 1])
+}
+
+
+impl ToPyObject for f64 {
+    fn to_object(&self, py: Python) -> PyObject {
+        PyFloat::new(py, *self).into()
+    }
+}
+
+impl IntoPyObject for f64 {
+    fn into_object(self, py: Python) -> PyObject {
+        PyFloat::new(py, self).into()
+    }
+}
+
+impl<'source> FromPyObject<'source> for f64 {
+    fn extract(ob: &'source PyObjectRef) -> PyResult<f64> {
+        let ptr = ob.as_ptr();
+        unsafe {
+            let value = if ffi::PyFloat_Check(ptr) != 0 {
+                ffi::PyFloat_AsDouble(ptr)
+            } else {
+                let num = ffi::PyNumber_Float(ptr);
+                if num.is_null() {
+                    return Err(PyErr::fetch(ob.py()));
+                }
+                let value = ffi::PyFloat_AsDouble(num);
+                ffi::Py_DECREF(num);
+                value
+            };
+            if value == -1.0 && PyErr::occurred(ob.py()) {
+                Err(PyErr::fetch(ob.py()))
+            } else {
+                Ok(value)
+            }
+        }
+    }
+}
+
+impl ToPyObject for f32 {
+    fn to_object(&self, py: Python) -> PyObject {
+        (*self as f64).to_object(py)
+    }
+}
+
+impl IntoPyObject for f32 {
+    fn into_object(self, py: Python) -> PyObject {
+        (self as f64).into_object(py)
+    }
+}
+
+impl<'source> FromPyObject<'source> for f32 {
+    fn extract(ob: &'source PyObjectRef) -> PyResult<f32> {
+        let value = try!(ob.extract::<f64>());
+        if value.is_finite() && value.abs() > ::std::f32::MAX as f64 {
+            Err(exc::OverflowError.into())
+        } else {
+            Ok(value as f32)
+        }
+    }
+}
+
+
 #[cfg(test)]
 mod test {
     use std;
     use python::Python;
     use conversion::ToPyObject;
+    use super::FromPyObjectExact;
+    #[cfg(feature = "num-bigint")]
+    use super::num_bigint::{BigInt, BigUint};
 
     macro_rules! num_to_py_object_and_back (
         ($func_name:ident, $t1:ty, $t2:ty) => (
@@ -190,6 +485,8 @@ mod test {
     num_to_py_object_and_back!(to_from_u64, u64, u64);
     num_to_py_object_and_back!(to_from_isize, isize, isize);
     num_to_py_object_and_back!(to_from_usize, usize, usize);
+    num_to_py_object_and_back!(to_from_i128, i128, i128);
+    num_to_py_object_and_back!(to_from_u128, u128, u128);
 
     #[test]
     fn test_u32_max() {
@@ -233,4 +530,123 @@ mod test {
         assert_eq!(v, obj.extract::<u64>(py).unwrap());
         assert!(obj.extract::<i64>(py).is_err());
     }
+
+    #[test]
+    fn test_i128_max() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let v = std::i128::MAX;
+        let obj = v.to_object(py);
+        assert_eq!(v, obj.extract::<i128>(py).unwrap());
+        assert!(obj.extract::<i64>(py).is_err());
+    }
+
+    #[test]
+    fn test_i128_min() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let v = std::i128::MIN;
+        let obj = v.to_object(py);
+        assert_eq!(v, obj.extract::<i128>(py).unwrap());
+        assert!(obj.extract::<i64>(py).is_err());
+    }
+
+    #[test]
+    fn test_u128_max() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let v = std::u128::MAX;
+        let obj = v.to_object(py);
+        assert_eq!(v, obj.extract::<u128>(py).unwrap());
+        assert!(obj.extract::<i128>(py).is_err());
+    }
+
+    num_to_py_object_and_back!(to_from_f32, f32, f32);
+    num_to_py_object_and_back!(to_from_f64, f64, f64);
+
+    #[test]
+    fn test_float_extract_from_int() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let obj = 42i32.to_object(py);
+        assert_eq!(42f64, obj.extract::<f64>(py).unwrap());
+    }
+
+    #[test]
+    fn test_f32_overflow() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let v = std::f64::MAX;
+        let obj = v.to_object(py);
+        assert!(obj.extract::<f32>(py).is_err());
+    }
+
+    #[test]
+    fn test_f32_infinity() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+
+        let obj = std::f64::INFINITY.to_object(py);
+        assert_eq!(std::f32::INFINITY, obj.extract::<f32>(py).unwrap());
+
+        let obj = std::f64::NEG_INFINITY.to_object(py);
+        assert_eq!(std::f32::NEG_INFINITY, obj.extract::<f32>(py).unwrap());
+    }
+
+    #[test]
+    fn test_extract_exact_rejects_float() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let obj = 3.5f64.to_object(py);
+        assert_eq!(3u64, obj.extract::<u64>(py).unwrap());
+        assert!(<u64 as FromPyObjectExact>::extract_exact(obj.as_ref(py)).is_err());
+    }
+
+    #[test]
+    fn test_extract_exact_accepts_int() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let obj = 42u64.to_object(py);
+        assert_eq!(42u64, <u64 as FromPyObjectExact>::extract_exact(obj.as_ref(py)).unwrap());
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn test_biguint_round_trip_beyond_u128() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let v = BigUint::from(std::u128::MAX) + BigUint::from(1u32);
+        let obj = v.to_object(py);
+        assert_eq!(v, obj.extract::<BigUint>(py).unwrap());
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn test_biguint_round_trip_zero() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let v = BigUint::from(0u32);
+        let obj = v.to_object(py);
+        assert_eq!(v, obj.extract::<BigUint>(py).unwrap());
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn test_bigint_round_trip_beyond_u128() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let v = BigInt::from(std::u128::MAX) + BigInt::from(1u32);
+        let obj = v.to_object(py);
+        assert_eq!(v, obj.extract::<BigInt>(py).unwrap());
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn test_bigint_round_trip_negative() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let v = -(BigInt::from(std::u128::MAX) + BigInt::from(1u32));
+        let obj = v.to_object(py);
+        assert_eq!(v, obj.extract::<BigInt>(py).unwrap());
+    }
 }